@@ -2,11 +2,13 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
     msg,
-    program::invoke,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
     system_instruction,
+    system_program,
     sysvar::{rent::Rent, Sysvar},
 };
 
@@ -23,10 +25,40 @@ pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instru
         CounterInstruction::IncrementCounter => {
             process_increment_counter(program_id, accounts)?
         }
+        CounterInstruction::SetCounter {value} => {
+            process_set_counter(program_id, accounts, value)?
+        }
+        CounterInstruction::DecrementCounter => {
+            process_decrement_counter(program_id, accounts)?
+        }
+        CounterInstruction::CloseCounter => {
+            process_close_counter(program_id, accounts)?
+        }
+        CounterInstruction::InitializeRecord {space} => {
+            process_initialize_record(program_id, accounts, space)?
+        }
+        CounterInstruction::WriteData {offset, data} => {
+            process_write_data(program_id, accounts, offset, data)?
+        }
+        CounterInstruction::ConditionalIncrement {expected, gate_program_id} => {
+            process_conditional_increment(program_id, accounts, expected, gate_program_id)?
+        }
+        CounterInstruction::MigrateCounter => {
+            process_migrate_counter(program_id, accounts)?
+        }
+        CounterInstruction::InitializeCounterPda {initial_value} => {
+            process_initialize_counter_pda(program_id, accounts, initial_value)?
+        }
+        CounterInstruction::IncrementViaCpi {target_program_id} => {
+            process_increment_via_cpi(program_id, accounts, target_program_id)?
+        }
     }
     Ok(())
 }
 
+// pda seed prefix
+pub const COUNTER_PDA_SEED: &[u8] = b"counter";
+
 //pub keyword
 pub fn process_initialize_counter(program_id: &Pubkey, accounts: &[AccountInfo], initial_value: u64) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
@@ -34,7 +66,7 @@ pub fn process_initialize_counter(program_id: &Pubkey, accounts: &[AccountInfo],
     let payer_account = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
 
-    let account_space = 8;
+    let account_space = CURRENT_ACCOUNT_SPACE;
     let rent = Rent::get()?;
     let required_lamports = rent.minimum_balance(account_space);
 
@@ -44,7 +76,9 @@ pub fn process_initialize_counter(program_id: &Pubkey, accounts: &[AccountInfo],
     )?;
 
     let counter_data = CounterAccount {
+        version: CURRENT_VERSION,
         count : initial_value,
+        authority: *payer_account.key,
     };
 
     let mut account_data = &mut counter_account.data.borrow_mut()[..];
@@ -53,6 +87,41 @@ pub fn process_initialize_counter(program_id: &Pubkey, accounts: &[AccountInfo],
 
     Ok(())
 }
+
+// same as above but the counter address is a derived PDA
+pub fn process_initialize_counter_pda(program_id: &Pubkey, accounts: &[AccountInfo], initial_value: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let payer_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let (expected_counter, bump) = Pubkey::find_program_address(&[COUNTER_PDA_SEED, payer_account.key.as_ref()], program_id);
+    if expected_counter != *counter_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let account_space = CURRENT_ACCOUNT_SPACE;
+    let rent = Rent::get()?;
+    let required_lamports = rent.minimum_balance(account_space);
+
+    invoke_signed(
+        &system_instruction::create_account(payer_account.key, counter_account.key, required_lamports, account_space as u64, program_id),
+        &[payer_account.clone(), counter_account.clone(), system_program.clone()],
+        &[&[COUNTER_PDA_SEED, payer_account.key.as_ref(), &[bump]]],
+    )?;
+
+    let counter_data = CounterAccount {
+        version: CURRENT_VERSION,
+        count: initial_value,
+        authority: *payer_account.key,
+    };
+
+    let mut account_data = &mut counter_account.data.borrow_mut()[..];
+    counter_data.serialize(&mut account_data).unwrap();
+    msg!("PDA counter initialized with initial data {}", initial_value);
+
+    Ok(())
+}
 pub fn process_increment_counter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     let counter_account = next_account_info(accounts_iter)?;
@@ -61,21 +130,269 @@ pub fn process_increment_counter(program_id: &Pubkey, accounts: &[AccountInfo])
         return Err(ProgramError::IncorrectProgramId);
     }
     let mut data = counter_account.data.borrow_mut();  //returns a smart pointer
+
+    // legacy accounts predate the version/authority fields and are just a
+    // bare 8-byte count; anything else is read as the current versioned layout
+    if data.len() == LEGACY_ACCOUNT_SPACE {
+        let count = u64::from_le_bytes(data[..8].try_into().unwrap());
+        let count = count.checked_add(1).ok_or(ProgramError::InvalidAccountData)?;
+        data[..8].copy_from_slice(&count.to_le_bytes());
+        return Ok(());
+    }
+
     let mut counter_data : CounterAccount = CounterAccount::try_from_slice(&data)?;
     counter_data.count = counter_data.count.checked_add(1).ok_or(ProgramError::InvalidAccountData)?;
     counter_data.serialize(&mut &mut data[..]).unwrap();     // learned here about smart pointers the deref trait and how i can use the same format as in the increment function inside the initialize function
-    Ok(()) 
-}   
+    Ok(())
+}
+
+// signer must match the counter's stored authority
+fn check_authority(counter_data: &CounterAccount, authority_account: &AccountInfo) -> ProgramResult {
+    if !authority_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if counter_data.authority != *authority_account.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+pub fn process_set_counter(program_id: &Pubkey, accounts: &[AccountInfo], value: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let authority_account = next_account_info(accounts_iter)?;
+
+    if *counter_account.owner != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = counter_account.data.borrow_mut();
+    let mut counter_data : CounterAccount = CounterAccount::try_from_slice(&data)?;
+    check_authority(&counter_data, authority_account)?;
+    counter_data.count = value;
+    counter_data.serialize(&mut &mut data[..]).unwrap();
+    msg!("Counter set to {}", value);
+    Ok(())
+}
+
+pub fn process_decrement_counter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let authority_account = next_account_info(accounts_iter)?;
+
+    if *counter_account.owner != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut data = counter_account.data.borrow_mut();
+    let mut counter_data : CounterAccount = CounterAccount::try_from_slice(&data)?;
+    check_authority(&counter_data, authority_account)?;
+    counter_data.count = counter_data.count.checked_sub(1).ok_or(ProgramError::InvalidAccountData)?;
+    counter_data.serialize(&mut &mut data[..]).unwrap();
+    Ok(())
+}
+
+pub fn process_close_counter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let authority_account = next_account_info(accounts_iter)?;
+    let destination_account = next_account_info(accounts_iter)?;
+
+    if *counter_account.owner != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let counter_data : CounterAccount = CounterAccount::try_from_slice(&counter_account.data.borrow())?;
+    check_authority(&counter_data, authority_account)?;
+
+    let dest_starting_lamports = destination_account.lamports();
+    **destination_account.lamports.borrow_mut() = dest_starting_lamports
+        .checked_add(counter_account.lamports())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    **counter_account.lamports.borrow_mut() = 0;
+
+    let mut data = counter_account.data.borrow_mut();
+    data.fill(0);
+    drop(data);
+    counter_account.assign(&system_program::id());
+    msg!("Counter account closed");
+    Ok(())
+}
+
+// bare record account addressed by offset, its first byte tagged with RECORD_DISCRIMINANT
+pub fn process_initialize_record(program_id: &Pubkey, accounts: &[AccountInfo], space: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let record_account = next_account_info(accounts_iter)?;
+    let payer_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let rent = Rent::get()?;
+    let required_lamports = rent.minimum_balance(space as usize);
+
+    invoke(
+        &system_instruction::create_account(payer_account.key, record_account.key, required_lamports, space, program_id),
+        &[payer_account.clone(), record_account.clone(), system_program.clone()],
+    )?;
+
+    if let Some(tag) = record_account.data.borrow_mut().first_mut() {
+        *tag = RECORD_DISCRIMINANT;
+    }
+
+    msg!("Record initialized with {} bytes of space", space);
+    Ok(())
+}
+
+// writes data into a record account at an offset
+pub fn process_write_data(program_id: &Pubkey, accounts: &[AccountInfo], offset: u64, data: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let record_account = next_account_info(accounts_iter)?;
+    let writer_account = next_account_info(accounts_iter)?;
+
+    if *record_account.owner != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if record_account.data.borrow().first() != Some(&RECORD_DISCRIMINANT) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !writer_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let offset = offset as usize;
+    let end = offset.checked_add(data.len()).ok_or(ProgramError::AccountDataTooSmall)?;
+    if end > record_account.data_len() {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    let mut account_data = record_account.data.borrow_mut();
+    account_data[offset..end].copy_from_slice(&data);
+    msg!("Wrote {} bytes at offset {}", data.len(), offset);
+    Ok(())
+}
+
+// increments only if the gate account's first 8 bytes match expected
+pub fn process_conditional_increment(program_id: &Pubkey, accounts: &[AccountInfo], expected: [u8; 8], gate_program_id: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let gate_account = next_account_info(accounts_iter)?;
+
+    if *counter_account.owner != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if *gate_account.owner != gate_program_id {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let gate_data = gate_account.data.borrow();
+    let witness = gate_data.get(..8).ok_or(ProgramError::InvalidAccountData)?;
+    if witness != expected {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    drop(gate_data);
+
+    let mut data = counter_account.data.borrow_mut();
+    let mut counter_data : CounterAccount = CounterAccount::try_from_slice(&data)?;
+    counter_data.count = counter_data.count.checked_add(1).ok_or(ProgramError::InvalidAccountData)?;
+    counter_data.serialize(&mut &mut data[..]).unwrap();
+    msg!("Gate condition satisfied, counter incremented");
+    Ok(())
+}
+
+// pre-version layout: bare count: u64
+pub const LEGACY_ACCOUNT_SPACE: usize = 8;
+pub const CURRENT_VERSION: u8 = 1;
+// version: u8 + count: u64 + authority: Pubkey
+pub const CURRENT_ACCOUNT_SPACE: usize = 1 + 8 + 32;
+// leading byte tagging an account created via InitializeRecord; WriteData only writes to tagged accounts
+// and MigrateCounter refuses them. The counter handlers do not check it, and a write at offset 0 replaces it.
+pub const RECORD_DISCRIMINANT: u8 = 0xFE;
+
+// drives another counter program's IncrementCounter via CPI
+pub fn process_increment_via_cpi(_program_id: &Pubkey, accounts: &[AccountInfo], target_program_id: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let target_counter_account = next_account_info(accounts_iter)?;
+    let target_program_account = next_account_info(accounts_iter)?;
+
+    if *target_program_account.key != target_program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let increment_instruction = Instruction::new_with_bytes(
+        target_program_id,
+        &[1],
+        vec![AccountMeta::new(*target_counter_account.key, false)],
+    );
+
+    invoke(&increment_instruction, &[target_counter_account.clone(), target_program_account.clone()])?;
+    msg!("Incremented counter {} via CPI", target_counter_account.key);
+
+    Ok(())
+}
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct CounterAccount {
-    count:u64,
+    version: u8,
+    count: u64,
+    authority: Pubkey,
+}
+
+// upgrades a legacy 8-byte counter to the current versioned layout
+pub fn process_migrate_counter(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let payer_account = next_account_info(accounts_iter)?;
+    let new_authority_account = next_account_info(accounts_iter)?;
+    let system_program_account = next_account_info(accounts_iter)?;
+
+    if *counter_account.owner != *program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !new_authority_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if counter_account.data_len() != LEGACY_ACCOUNT_SPACE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if counter_account.data.borrow().first() == Some(&RECORD_DISCRIMINANT) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let old_count = u64::from_le_bytes(counter_account.data.borrow()[..8].try_into().unwrap());
+
+    let rent = Rent::get()?;
+    let required_lamports = rent.minimum_balance(CURRENT_ACCOUNT_SPACE);
+    let shortfall = required_lamports.saturating_sub(counter_account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer_account.key, counter_account.key, shortfall),
+            &[payer_account.clone(), counter_account.clone(), system_program_account.clone()],
+        )?;
+    }
+
+    counter_account.realloc(CURRENT_ACCOUNT_SPACE, true)?;
+
+    let counter_data = CounterAccount {
+        version: CURRENT_VERSION,
+        count: old_count,
+        authority: *new_authority_account.key,
+    };
+    let mut account_data = &mut counter_account.data.borrow_mut()[..];
+    counter_data.serialize(&mut account_data).unwrap();
+    msg!("Counter migrated to version {}", CURRENT_VERSION);
+
+    Ok(())
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum CounterInstruction {
     InitializeCounter {initial_value: u64},  // variant 0
     IncrementCounter,                        // variant 1
+    SetCounter {value: u64},                 // variant 2
+    DecrementCounter,                        // variant 3
+    CloseCounter,                            // variant 4
+    InitializeRecord {space: u64},           // variant 5
+    WriteData {offset: u64, data: Vec<u8>},  // variant 6
+    ConditionalIncrement {expected: [u8; 8], gate_program_id: Pubkey}, // variant 7
+    MigrateCounter,                          // variant 8
+    InitializeCounterPda {initial_value: u64}, // variant 9
+    IncrementViaCpi {target_program_id: Pubkey}, // variant 10
 }
 
 impl CounterInstruction {
@@ -85,11 +402,46 @@ impl CounterInstruction {
         match variant {
             0 => {
                 let initial_value = u64::from_le_bytes(rest.try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
-                Ok(Self::InitializeCounter {initial_value})     
+                Ok(Self::InitializeCounter {initial_value})
         }
         1 => {
             Ok(Self::IncrementCounter)
         }
+        2 => {
+            let value = u64::from_le_bytes(rest.try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+            Ok(Self::SetCounter {value})
+        }
+        3 => {
+            Ok(Self::DecrementCounter)
+        }
+        4 => {
+            Ok(Self::CloseCounter)
+        }
+        5 => {
+            let space = u64::from_le_bytes(rest.try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+            Ok(Self::InitializeRecord {space})
+        }
+        6 => {
+            let offset = u64::from_le_bytes(rest.get(0..8).ok_or(ProgramError::InvalidInstructionData)?.try_into().unwrap());
+            let data = rest.get(8..).ok_or(ProgramError::InvalidInstructionData)?.to_vec();
+            Ok(Self::WriteData {offset, data})
+        }
+        7 => {
+            let expected : [u8; 8] = rest.get(0..8).ok_or(ProgramError::InvalidInstructionData)?.try_into().unwrap();
+            let gate_program_id = Pubkey::try_from(rest.get(8..40).ok_or(ProgramError::InvalidInstructionData)?).map_err(|_| ProgramError::InvalidInstructionData)?;
+            Ok(Self::ConditionalIncrement {expected, gate_program_id})
+        }
+        8 => {
+            Ok(Self::MigrateCounter)
+        }
+        9 => {
+            let initial_value = u64::from_le_bytes(rest.try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+            Ok(Self::InitializeCounterPda {initial_value})
+        }
+        10 => {
+            let target_program_id = Pubkey::try_from(rest.get(0..32).ok_or(ProgramError::InvalidInstructionData)?).map_err(|_| ProgramError::InvalidInstructionData)?;
+            Ok(Self::IncrementViaCpi {target_program_id})
+        }
         _ => {
             Err(ProgramError::InvalidInstructionData)
         }
@@ -139,22 +491,278 @@ async fn test_counter_program() {
         assert_eq!(counter.count , 48);
         println!("Counter initilaized successfully with value {}", counter.count);
     }
-}
 
     //testing the increment instruction
     let mut init_instruction_data2 = vec![1];
-    let increment_instruction = Instruction::new_with_bytes(program_id, &init_isntruction_data2, vec![
+    let increment_instruction = Instruction::new_with_bytes(program_id, &init_instruction_data2, vec![
         AccountMeta::new(counter_keypair.pubkey(), true),
     ]);
     let mut transaction2 = Transaction::new_with_payer(&[increment_instruction], Some(payer.pubkey()));
-    transaction.sign(&[&payer, &counter_keypair], recent_blockhash);
-    banks_client.process_transaction(transaction).await.unwrap();
+    transaction2.sign(&[&payer, &counter_keypair], recent_blockhash);
+    banks_client.process_transaction(transaction2).await.unwrap();
 
     //once again we check whether the incrementation has happened
     let account2 = banks_client.get_account(counter_keypair.pubkey()).await.expect("Failed to get the account");
     if let Some(account_data2) = account2 {
         let counter2 : CounterAccount = CounterAccount::try_from_slice(&account_data2.data).expect("Failed to deserialize");
-        asserteq!(counter2.count, 49);
+        assert_eq!(counter2.count, 49);
         println!("Counter has been incremented successfully and the current count is {}", counter2.count);
     }
+}
+
+#[tokio::test]
+async fn test_set_decrement_close_counter() {
+    let program_id = Pubkey::new_unique();
+    let (mut banks_client, payer, recent_blockhash) = ProgramTest::new("counter_program", program_id, processor!(process_instruction)).start().await;
+    let counter_keypair = Keypair::new();
+    let initial_val: u64 = 48;
+
+    let mut init_instruction_data = vec![0];
+    init_instruction_data.extend_from_slice(&initial_val.to_le_bytes());
+    let initialize_instruction = Instruction::new_with_bytes(program_id, &init_instruction_data, vec![
+        AccountMeta::new(counter_keypair.pubkey(), true),
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    let mut transaction = Transaction::new_with_payer(&[initialize_instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &counter_keypair], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // set the counter to 100
+    let mut set_instruction_data = vec![2];
+    set_instruction_data.extend_from_slice(&100u64.to_le_bytes());
+    let set_instruction = Instruction::new_with_bytes(program_id, &set_instruction_data, vec![
+        AccountMeta::new(counter_keypair.pubkey(), false),
+        AccountMeta::new_readonly(payer.pubkey(), true),
+    ]);
+    let mut transaction2 = Transaction::new_with_payer(&[set_instruction], Some(&payer.pubkey()));
+    transaction2.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction2).await.unwrap();
+
+    let account = banks_client.get_account(counter_keypair.pubkey()).await.expect("Failed to get the account");
+    if let Some(account_data) = account {
+        let counter : CounterAccount = CounterAccount::try_from_slice(&account_data.data).expect("Failed to deserialize");
+        assert_eq!(counter.count, 100);
+        println!("Counter set successfully to {}", counter.count);
+    }
+
+    // decrement the counter
+    let decrement_instruction = Instruction::new_with_bytes(program_id, &[3], vec![
+        AccountMeta::new(counter_keypair.pubkey(), false),
+        AccountMeta::new_readonly(payer.pubkey(), true),
+    ]);
+    let mut transaction3 = Transaction::new_with_payer(&[decrement_instruction], Some(&payer.pubkey()));
+    transaction3.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction3).await.unwrap();
+
+    let account2 = banks_client.get_account(counter_keypair.pubkey()).await.expect("Failed to get the account");
+    if let Some(account_data2) = account2 {
+        let counter2 : CounterAccount = CounterAccount::try_from_slice(&account_data2.data).expect("Failed to deserialize");
+        assert_eq!(counter2.count, 99);
+        println!("Counter decremented successfully to {}", counter2.count);
+    }
+
+    // close the counter, lamports should move to the destination
+    let destination = Keypair::new();
+    let close_instruction = Instruction::new_with_bytes(program_id, &[4], vec![
+        AccountMeta::new(counter_keypair.pubkey(), false),
+        AccountMeta::new_readonly(payer.pubkey(), true),
+        AccountMeta::new(destination.pubkey(), false),
+    ]);
+    let mut transaction4 = Transaction::new_with_payer(&[close_instruction], Some(&payer.pubkey()));
+    transaction4.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction4).await.unwrap();
+
+    let closed_account = banks_client.get_account(counter_keypair.pubkey()).await.expect("Failed to get the account");
+    if let Some(closed_account_data) = closed_account {
+        assert_eq!(closed_account_data.lamports, 0);
+        assert_eq!(closed_account_data.owner, system_program::id());
+    }
+
+    let dest_account = banks_client.get_account(destination.pubkey()).await.expect("Failed to get the account");
+    if let Some(dest_account_data) = dest_account {
+        assert!(dest_account_data.lamports > 0);
+        println!("Counter closed, lamports reclaimed by destination");
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_record_and_write_data() {
+    let program_id = Pubkey::new_unique();
+    let (mut banks_client, payer, recent_blockhash) = ProgramTest::new("counter_program", program_id, processor!(process_instruction)).start().await;
+    let record_keypair = Keypair::new();
+
+    let mut init_instruction_data = vec![5];
+    init_instruction_data.extend_from_slice(&16u64.to_le_bytes());
+    let initialize_instruction = Instruction::new_with_bytes(program_id, &init_instruction_data, vec![
+        AccountMeta::new(record_keypair.pubkey(), true),
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    let mut transaction = Transaction::new_with_payer(&[initialize_instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &record_keypair], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(record_keypair.pubkey()).await.expect("Failed to get the account");
+    if let Some(account_data) = account {
+        assert_eq!(account_data.data[0], RECORD_DISCRIMINANT);
+    }
+
+    let mut write_instruction_data = vec![6];
+    write_instruction_data.extend_from_slice(&1u64.to_le_bytes());
+    write_instruction_data.extend_from_slice(&[7, 7, 7, 7]);
+    let write_instruction = Instruction::new_with_bytes(program_id, &write_instruction_data, vec![
+        AccountMeta::new(record_keypair.pubkey(), false),
+        AccountMeta::new_readonly(payer.pubkey(), true),
+    ]);
+    let mut transaction2 = Transaction::new_with_payer(&[write_instruction], Some(&payer.pubkey()));
+    transaction2.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction2).await.unwrap();
+
+    let account2 = banks_client.get_account(record_keypair.pubkey()).await.expect("Failed to get the account");
+    if let Some(account_data2) = account2 {
+        assert_eq!(&account_data2.data[1..5], &[7, 7, 7, 7]);
+        println!("Record written successfully at offset 1");
+    }
+}
+
+#[tokio::test]
+async fn test_conditional_increment() {
+    let program_id = Pubkey::new_unique();
+    let (mut banks_client, payer, recent_blockhash) = ProgramTest::new("counter_program", program_id, processor!(process_instruction)).start().await;
+
+    let counter_keypair = Keypair::new();
+    let initial_val: u64 = 48;
+    let mut init_instruction_data = vec![0];
+    init_instruction_data.extend_from_slice(&initial_val.to_le_bytes());
+    let initialize_instruction = Instruction::new_with_bytes(program_id, &init_instruction_data, vec![
+        AccountMeta::new(counter_keypair.pubkey(), true),
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    let mut transaction = Transaction::new_with_payer(&[initialize_instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &counter_keypair], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // a gate record, owned by this same program, witnessing an 8-byte flag
+    let gate_keypair = Keypair::new();
+    let expected: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    let mut gate_init_data = vec![5];
+    gate_init_data.extend_from_slice(&8u64.to_le_bytes());
+    let gate_init_instruction = Instruction::new_with_bytes(program_id, &gate_init_data, vec![
+        AccountMeta::new(gate_keypair.pubkey(), true),
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    let mut transaction2 = Transaction::new_with_payer(&[gate_init_instruction], Some(&payer.pubkey()));
+    transaction2.sign(&[&payer, &gate_keypair], recent_blockhash);
+    banks_client.process_transaction(transaction2).await.unwrap();
+
+    let mut gate_write_data = vec![6];
+    gate_write_data.extend_from_slice(&0u64.to_le_bytes());
+    gate_write_data.extend_from_slice(&expected);
+    let gate_write_instruction = Instruction::new_with_bytes(program_id, &gate_write_data, vec![
+        AccountMeta::new(gate_keypair.pubkey(), false),
+        AccountMeta::new_readonly(payer.pubkey(), true),
+    ]);
+    let mut transaction3 = Transaction::new_with_payer(&[gate_write_instruction], Some(&payer.pubkey()));
+    transaction3.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction3).await.unwrap();
+
+    // the gate now witnesses `expected`, so the conditional increment should succeed
+    let mut conditional_instruction_data = vec![7];
+    conditional_instruction_data.extend_from_slice(&expected);
+    conditional_instruction_data.extend_from_slice(program_id.as_ref());
+    let conditional_instruction = Instruction::new_with_bytes(program_id, &conditional_instruction_data, vec![
+        AccountMeta::new(counter_keypair.pubkey(), false),
+        AccountMeta::new_readonly(gate_keypair.pubkey(), false),
+    ]);
+    let mut transaction4 = Transaction::new_with_payer(&[conditional_instruction], Some(&payer.pubkey()));
+    transaction4.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction4).await.unwrap();
+
+    let account = banks_client.get_account(counter_keypair.pubkey()).await.expect("Failed to get the account");
+    if let Some(account_data) = account {
+        let counter : CounterAccount = CounterAccount::try_from_slice(&account_data.data).expect("Failed to deserialize");
+        assert_eq!(counter.count, 49);
+        println!("Counter incremented via satisfied gate condition, now at {}", counter.count);
+    }
+}
+
+#[tokio::test]
+async fn test_counter_program_pda() {
+    let program_id = Pubkey::new_unique();
+    let (mut banks_client, payer, recent_blockhash) = ProgramTest::new("counter_program", program_id, processor!(process_instruction)).start().await;
+
+    let (counter_pda, _bump) = Pubkey::find_program_address(&[COUNTER_PDA_SEED, payer.pubkey().as_ref()], &program_id);
+    let initial_val: u64 = 48;
+
+    let mut init_instruction_data = vec![9];
+    init_instruction_data.extend_from_slice(&initial_val.to_le_bytes());
+
+    let initialize_instruction = Instruction::new_with_bytes(program_id, &init_instruction_data, vec![
+        AccountMeta::new(counter_pda, false),
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+
+    let mut transaction = Transaction::new_with_payer(&[initialize_instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = banks_client.get_account(counter_pda).await.expect("Failed to get the account");
+    if let Some(account_data) = account {
+        let counter : CounterAccount = CounterAccount::try_from_slice(&account_data.data).expect("Failed to deserialize");
+        assert_eq!(counter.count, 48);
+        println!("PDA counter initialized successfully with value {}", counter.count);
+    }
+}
+
+#[tokio::test]
+async fn test_increment_via_cpi() {
+    let program_a_id = Pubkey::new_unique();
+    let program_b_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new("counter_program", program_a_id, processor!(process_instruction));
+    program_test.add_program("counter_program", program_b_id, processor!(process_instruction));
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    // initialize program B's counter to 48
+    let counter_keypair = Keypair::new();
+    let initial_val: u64 = 48;
+    let mut init_instruction_data = vec![0];
+    init_instruction_data.extend_from_slice(&initial_val.to_le_bytes());
+
+    let initialize_instruction = Instruction::new_with_bytes(program_b_id, &init_instruction_data, vec![
+        AccountMeta::new(counter_keypair.pubkey(), true),
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    let mut transaction = Transaction::new_with_payer(&[initialize_instruction], Some(&payer.pubkey()));
+    transaction.sign(&[&payer, &counter_keypair], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let before = banks_client.get_account(counter_keypair.pubkey()).await.expect("Failed to get the account").unwrap();
+    let counter_before : CounterAccount = CounterAccount::try_from_slice(&before.data).expect("Failed to deserialize");
+    assert_eq!(counter_before.count, 48);
+
+    // program A drives program B's counter from 48 to 49 via CPI
+    let mut cpi_instruction_data = vec![10];
+    cpi_instruction_data.extend_from_slice(program_b_id.as_ref());
+    let increment_via_cpi_instruction = Instruction::new_with_bytes(program_a_id, &cpi_instruction_data, vec![
+        AccountMeta::new(counter_keypair.pubkey(), false),
+        AccountMeta::new_readonly(program_b_id, false),
+    ]);
+    let mut transaction2 = Transaction::new_with_payer(&[increment_via_cpi_instruction], Some(&payer.pubkey()));
+    transaction2.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction2).await.unwrap();
+
+    let account = banks_client.get_account(counter_keypair.pubkey()).await.expect("Failed to get the account");
+    if let Some(account_data) = account {
+        let counter : CounterAccount = CounterAccount::try_from_slice(&account_data.data).expect("Failed to deserialize");
+        assert_eq!(counter.count, 49);
+        println!("Counter driven from program A to program B via CPI, now at {}", counter.count);
+    }
+}
 }
\ No newline at end of file